@@ -0,0 +1,119 @@
+use std::io::Cursor;
+
+use rodio::{Decoder, OutputStream, Sink};
+use tokio::sync::mpsc::{self, UnboundedSender};
+
+/// 播放线程能够响应的事件。Tauri 命令把它们通过 channel 转发给
+/// 持有 `Sink` 的专用线程，避免把非 `Send` 的音频句柄暴露到 async 世界。
+pub enum AudioEvent {
+    Play(String),
+    Pause,
+    Resume,
+    Stop,
+    Volume(f32),
+}
+
+/// 音频播放子系统。通过 `tauri::Builder::manage` 注册为全局状态，
+/// 命令只持有一个向播放线程发送 `AudioEvent` 的发送端。
+pub struct AudioService {
+    tx: UnboundedSender<AudioEvent>,
+}
+
+impl AudioService {
+    /// 启动一个专用播放线程并返回可被 `manage` 的服务句柄。
+    pub fn new() -> Self {
+        let (tx, mut rx) = mpsc::unbounded_channel::<AudioEvent>();
+
+        std::thread::spawn(move || {
+            // `OutputStream` 必须在该线程内存活，否则音频设备会被释放。
+            let (_stream, handle) = match OutputStream::try_default() {
+                Ok(v) => v,
+                Err(e) => {
+                    eprintln!("无法打开音频输出设备：{}", e);
+                    return;
+                }
+            };
+            let sink = match Sink::try_new(&handle) {
+                Ok(s) => s,
+                Err(e) => {
+                    eprintln!("无法创建播放队列：{}", e);
+                    return;
+                }
+            };
+
+            while let Some(event) = rx.blocking_recv() {
+                match event {
+                    AudioEvent::Play(url) => {
+                        // 清空上一段音频，换成新的 URL。
+                        sink.stop();
+                        match fetch_source(&url) {
+                            Ok(decoder) => sink.append(decoder),
+                            Err(e) => eprintln!("加载音频失败：{}", e),
+                        }
+                        sink.play();
+                    }
+                    AudioEvent::Pause => sink.pause(),
+                    AudioEvent::Resume => sink.play(),
+                    AudioEvent::Stop => sink.stop(),
+                    AudioEvent::Volume(v) => sink.set_volume(v),
+                }
+            }
+        });
+
+        Self { tx }
+    }
+
+    fn send(&self, event: AudioEvent) -> Result<(), String> {
+        self.tx
+            .send(event)
+            .map_err(|_| "播放线程已退出".to_string())
+    }
+}
+
+impl Default for AudioService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 同步地把远端音频拉取到内存，包装成可解码的数据源。
+/// 运行在播放线程上，因此使用阻塞式 `reqwest`。
+fn fetch_source(url: &str) -> Result<Decoder<Cursor<Vec<u8>>>, String> {
+    let bytes = reqwest::blocking::get(url)
+        .map_err(|e| format!("下载失败：{}", e))?
+        .bytes()
+        .map_err(|e| format!("读取失败：{}", e))?
+        .to_vec();
+
+    Decoder::new(Cursor::new(bytes)).map_err(|e| format!("解码失败：{}", e))
+}
+
+/// 播放指定 URL 的音频，会替换当前正在播放的内容。
+#[tauri::command]
+pub fn play_audio(service: tauri::State<'_, AudioService>, url: String) -> Result<(), String> {
+    service.send(AudioEvent::Play(url))
+}
+
+/// 暂停播放。
+#[tauri::command]
+pub fn pause(service: tauri::State<'_, AudioService>) -> Result<(), String> {
+    service.send(AudioEvent::Pause)
+}
+
+/// 从暂停处继续播放。
+#[tauri::command]
+pub fn resume(service: tauri::State<'_, AudioService>) -> Result<(), String> {
+    service.send(AudioEvent::Resume)
+}
+
+/// 停止播放并清空队列。
+#[tauri::command]
+pub fn stop(service: tauri::State<'_, AudioService>) -> Result<(), String> {
+    service.send(AudioEvent::Stop)
+}
+
+/// 设置音量，`1.0` 为原始音量。
+#[tauri::command]
+pub fn set_volume(service: tauri::State<'_, AudioService>, volume: f32) -> Result<(), String> {
+    service.send(AudioEvent::Volume(volume))
+}
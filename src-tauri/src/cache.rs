@@ -0,0 +1,95 @@
+use std::path::PathBuf;
+
+use sha2::{Digest, Sha256};
+use tauri::Manager;
+
+/// 计算缓存键：`text` 与 `format` 拼接后的 SHA-256 十六进制串，
+/// 保证同一段文本、同一格式总是命中同一个文件。
+fn cache_key(text: &str, format: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(text.as_bytes());
+    hasher.update(b"\0");
+    hasher.update(format.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// 解析并确保缓存目录存在，即应用的缓存目录（`app_cache_dir`）。
+fn cache_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| format!("无法解析缓存目录：{}", e))?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("创建缓存目录失败：{}", e))?;
+    Ok(dir)
+}
+
+/// 带缓存的语音合成：命中缓存时立即返回本地文件路径，
+/// 未命中时调用远端 API 合成并下载一次到缓存目录，再返回其路径。
+#[tauri::command]
+pub async fn synthesize_cached(
+    app: tauri::AppHandle,
+    text: String,
+    api_key: String,
+    format: String,
+) -> Result<String, String> {
+    let fmt = if format == "wav" { "wav" } else { "mp3" };
+    let path = cache_dir(&app)?.join(format!("{}.{}", cache_key(&text, fmt), fmt));
+
+    // 命中缓存：直接返回已有文件。
+    if path.exists() {
+        return Ok(file_url(&path));
+    }
+
+    // 未命中：合成并下载一次。
+    let url = crate::synthesize_speech(text, api_key, fmt.to_string()).await?;
+    let bytes = reqwest::get(&url)
+        .await
+        .map_err(|e| format!("下载失败：{}", e))?
+        .bytes()
+        .await
+        .map_err(|e| format!("读取失败：{}", e))?;
+    std::fs::write(&path, &bytes).map_err(|e| format!("写入缓存失败：{}", e))?;
+
+    Ok(file_url(&path))
+}
+
+/// 把本地缓存文件路径转换成 `file://` URL，供 webview 的 `<audio>` 在
+/// 资源协议/CSP 下直接加载（前端也可对其调用 `convertFileSrc`）。
+fn file_url(path: &std::path::Path) -> String {
+    use tauri::Url;
+
+    match Url::from_file_path(path) {
+        Ok(url) => url.to_string(),
+        Err(()) => path.to_string_lossy().into_owned(),
+    }
+}
+
+/// 清空缓存目录中的所有音频文件。
+#[tauri::command]
+pub fn clear_cache(app: tauri::AppHandle) -> Result<(), String> {
+    let dir = cache_dir(&app)?;
+    for entry in std::fs::read_dir(&dir).map_err(|e| format!("读取缓存目录失败：{}", e))? {
+        let entry = entry.map_err(|e| format!("遍历缓存目录失败：{}", e))?;
+        if entry.path().is_file() {
+            std::fs::remove_file(entry.path())
+                .map_err(|e| format!("删除缓存文件失败：{}", e))?;
+        }
+    }
+    Ok(())
+}
+
+/// 统计缓存目录占用的总字节数，供前端展示磁盘用量。
+#[tauri::command]
+pub fn cache_size(app: tauri::AppHandle) -> Result<u64, String> {
+    let dir = cache_dir(&app)?;
+    let mut total = 0u64;
+    for entry in std::fs::read_dir(&dir).map_err(|e| format!("读取缓存目录失败：{}", e))? {
+        let entry = entry.map_err(|e| format!("遍历缓存目录失败：{}", e))?;
+        if let Ok(meta) = entry.metadata() {
+            if meta.is_file() {
+                total += meta.len();
+            }
+        }
+    }
+    Ok(total)
+}
@@ -1,6 +1,34 @@
+mod audio;
+mod cache;
+mod synth_long;
+mod tray;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use audio::AudioService;
+use serde::Serialize;
+use tauri::{Emitter, Manager};
+
+/// 下载进度，随 `download-progress` 事件发送到前端渲染进度条。
+#[derive(Clone, Serialize)]
+struct Progress {
+    downloaded: u64,
+    total: u64,
+}
+
+/// 共享的下载取消标志。`cancel_download` 置位，下载循环在每个分块之间检查。
+#[derive(Default)]
+struct DownloadState {
+    cancelled: AtomicBool,
+}
+
 /// 调用曼波 TTS API，解析 JSON 响应，直接返回音频 URL。
 #[tauri::command]
-async fn synthesize_speech(text: String, api_key: String, format: String) -> Result<String, String> {
+pub(crate) async fn synthesize_speech(
+    text: String,
+    api_key: String,
+    format: String,
+) -> Result<String, String> {
     let client = reqwest::Client::new();
     // 只允许 mp3 / wav，其余回退到 mp3
     let fmt = if format == "wav" { "wav" } else { "mp3" };
@@ -37,6 +65,9 @@ async fn synthesize_speech(text: String, api_key: String, format: String) -> Res
 }
 
 /// 从 URL 下载音频文件，弹出系统保存对话框让用户选择保存位置。
+///
+/// 下载采用流式写入：读取 `Content-Length` 作为总大小，逐块写入目标文件，
+/// 每块结束后发送 `download-progress` 事件，并检查取消标志以支持中途取消。
 #[tauri::command]
 async fn save_audio(app: tauri::AppHandle, url: String) -> Result<(), String> {
     use tauri_plugin_dialog::DialogExt;
@@ -61,26 +92,158 @@ async fn save_audio(app: tauri::AppHandle, url: String) -> Result<(), String> {
         _ => return Ok(()),
     };
 
-    // ② 用户选好路径后才开始下载
-    let bytes = reqwest::get(&url)
-        .await
-        .map_err(|e| format!("下载失败：{}", e))?
-        .bytes()
+    // ② 用户选好路径后才开始流式下载
+    download_to_file(&app, &url, &path).await
+}
+
+/// 流式下载 `url` 到 `path`，边写边发送进度事件，可被 `cancel_download` 中断。
+async fn download_to_file(
+    app: &tauri::AppHandle,
+    url: &str,
+    path: &std::path::Path,
+) -> Result<(), String> {
+    use std::io::Write;
+
+    use futures_util::StreamExt;
+
+    // 每次下载开始前复位取消标志。
+    let state = app.state::<DownloadState>();
+    state.cancelled.store(false, Ordering::SeqCst);
+
+    let response = reqwest::get(url)
         .await
-        .map_err(|e| format!("读取失败：{}", e))?;
+        .map_err(|e| format!("下载失败：{}", e))?;
+
+    let total = response.content_length().unwrap_or(0);
+
+    let mut file = std::fs::File::create(path).map_err(|e| format!("创建文件失败：{}", e))?;
+    let mut downloaded: u64 = 0;
+    let mut stream = response.bytes_stream();
 
-    std::fs::write(&path, &bytes)
-        .map_err(|e| format!("写入文件失败：{}", e))?;
+    while let Some(chunk) = stream.next().await {
+        // 分块之间检查取消标志，取消时删除未完成的文件。
+        if state.cancelled.load(Ordering::SeqCst) {
+            drop(file);
+            let _ = std::fs::remove_file(path);
+            return Err("下载已取消".to_string());
+        }
+
+        let chunk = chunk.map_err(|e| format!("读取失败：{}", e))?;
+        file.write_all(&chunk)
+            .map_err(|e| format!("写入文件失败：{}", e))?;
+        downloaded += chunk.len() as u64;
+
+        app.emit("download-progress", Progress { downloaded, total })
+            .map_err(|e| format!("发送进度事件失败：{}", e))?;
+    }
 
     Ok(())
 }
 
+/// 发送一条 “语音合成完成” 的桌面通知，即便窗口已最小化也会提示用户。
+pub(crate) fn notify_done(app: &tauri::AppHandle, body: &str) {
+    use tauri_plugin_notification::NotificationExt;
+
+    let _ = app
+        .notification()
+        .builder()
+        .title("语音合成完成")
+        .body(body)
+        .show();
+}
+
+/// 免对话框的快速保存：把音频下载到操作系统的音频目录
+/// （macOS 的 `~/Music`、Windows 的 `音乐` 目录、Linux 的 `XDG_MUSIC_DIR`），
+/// 可选地放入子文件夹，文件名由 URL 加时间戳派生以避免冲突，返回最终路径。
+#[tauri::command]
+async fn save_audio_auto(
+    app: tauri::AppHandle,
+    url: String,
+    subfolder: Option<String>,
+) -> Result<String, String> {
+    let mut dir = app
+        .path()
+        .audio_dir()
+        .map_err(|e| format!("无法解析音频目录：{}", e))?;
+    if let Some(sub) = subfolder.filter(|s| !s.trim().is_empty()) {
+        dir.push(sub);
+    }
+    std::fs::create_dir_all(&dir).map_err(|e| format!("创建目录失败：{}", e))?;
+
+    let path = unique_path(&dir, &collision_safe_name(&url));
+    download_to_file(&app, &url, &path).await?;
+    notify_done(&app, "保存完成");
+    Ok(path.to_string_lossy().into_owned())
+}
+
+/// 根据 URL 的文件名与当前时间戳拼出不易冲突的文件名，
+/// 时间戳插在扩展名之前（如 `audio-1700000000.mp3`）。
+fn collision_safe_name(url: &str) -> String {
+    let raw = url.split('/').last().unwrap_or("audio.mp3");
+    let ts = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    match raw.rsplit_once('.') {
+        Some((stem, ext)) => format!("{}-{}.{}", stem, ts, ext),
+        None => format!("{}-{}", raw, ts),
+    }
+}
+
+/// 在 `dir` 下把 `name` 变成尚不存在的路径，冲突时在扩展名前追加 `-1`、`-2`……
+/// 以防同一秒内多次快速保存互相覆盖（整秒时间戳不足以区分）。
+fn unique_path(dir: &std::path::Path, name: &str) -> std::path::PathBuf {
+    let candidate = dir.join(name);
+    if !candidate.exists() {
+        return candidate;
+    }
+    let (stem, ext) = match name.rsplit_once('.') {
+        Some((s, e)) => (s.to_string(), format!(".{}", e)),
+        None => (name.to_string(), String::new()),
+    };
+    let mut n = 1;
+    loop {
+        let candidate = dir.join(format!("{}-{}{}", stem, n, ext));
+        if !candidate.exists() {
+            return candidate;
+        }
+        n += 1;
+    }
+}
+
+/// 请求取消正在进行的下载，下载循环会在下一个分块处中止。
+#[tauri::command]
+fn cancel_download(state: tauri::State<'_, DownloadState>) {
+    state.cancelled.store(true, Ordering::SeqCst);
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
-        .invoke_handler(tauri::generate_handler![synthesize_speech, save_audio])
+        .plugin(tauri_plugin_notification::init())
+        .manage(AudioService::new())
+        .manage(DownloadState::default())
+        .setup(|app| {
+            tray::setup(app.handle())?;
+            Ok(())
+        })
+        .invoke_handler(tauri::generate_handler![
+            synthesize_speech,
+            save_audio,
+            save_audio_auto,
+            cancel_download,
+            cache::synthesize_cached,
+            cache::clear_cache,
+            cache::cache_size,
+            synth_long::synthesize_long,
+            audio::play_audio,
+            audio::pause,
+            audio::resume,
+            audio::stop,
+            audio::set_volume,
+        ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
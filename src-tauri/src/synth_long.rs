@@ -0,0 +1,340 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use sha2::{Digest, Sha256};
+use tauri::{Emitter, Manager};
+use tokio::sync::Semaphore;
+
+/// 单个分块的最大字符数，超过此长度远端 `mbAIsc` 接口可能拒绝或截断。
+const MAX_CHUNK_LEN: usize = 200;
+
+/// 达到此长度后遇到句子边界即切块，避免为了凑满 `MAX_CHUNK_LEN` 把句子切开。
+const MIN_CHUNK_LEN: usize = 80;
+
+/// 并发下载分块的上限，避免一次性打满远端接口。
+const MAX_CONCURRENCY: usize = 4;
+
+/// 合成进度，随 `synthesize-progress` 事件发送到前端（如 “3/12 段已完成”）。
+#[derive(Clone, serde::Serialize)]
+struct SegmentProgress {
+    done: usize,
+    total: usize,
+}
+
+/// 按句子边界（中英文标点与换行）把长文本切成不超过 `MAX_CHUNK_LEN` 的分块。
+fn split_sentences(text: &str) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+
+    for ch in text.chars() {
+        current.push(ch);
+        let is_boundary = matches!(ch, '。' | '！' | '？' | '.' | '!' | '?' | '\n');
+        let len = current.chars().count();
+        if is_boundary && len >= MIN_CHUNK_LEN {
+            // 达到最小长度后在句子边界处收尾，让分块落在标点上。
+            chunks.push(std::mem::take(&mut current));
+        } else if len >= MAX_CHUNK_LEN {
+            // 没遇到标点但已超长，作为兜底强制在此切断。
+            chunks.push(std::mem::take(&mut current));
+        }
+    }
+    if !current.trim().is_empty() {
+        chunks.push(current);
+    }
+    chunks
+        .into_iter()
+        .filter(|c| !c.trim().is_empty())
+        .collect()
+}
+
+/// 长文本合成：分句 → 并发逐段合成下载（信号量限流）→ 拼接成单个文件。
+/// 每段完成后发送 `synthesize-progress` 事件，返回合并后文件的路径。
+#[tauri::command]
+pub async fn synthesize_long(
+    app: tauri::AppHandle,
+    text: String,
+    api_key: String,
+    format: String,
+) -> Result<String, String> {
+    let fmt = if format == "wav" { "wav" } else { "mp3" };
+    let chunks = split_sentences(&text);
+    if chunks.is_empty() {
+        return Err("文本为空".to_string());
+    }
+    let total = chunks.len();
+
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENCY));
+    let done = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+
+    // 逐段并发合成，保留顺序（用下标收集后排序）。
+    let mut handles = Vec::with_capacity(total);
+    for (index, chunk) in chunks.into_iter().enumerate() {
+        let app = app.clone();
+        let api_key = api_key.clone();
+        let semaphore = semaphore.clone();
+        let done = done.clone();
+        let fmt = fmt.to_string();
+        handles.push(tokio::spawn(async move {
+            let _permit = semaphore
+                .acquire()
+                .await
+                .map_err(|_| "信号量已关闭".to_string())?;
+            let bytes = fetch_segment(chunk, api_key, fmt).await?;
+
+            // 每段完成后上报进度。
+            let completed = done.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+            let _ = app.emit(
+                "synthesize-progress",
+                SegmentProgress {
+                    done: completed,
+                    total,
+                },
+            );
+            Ok::<(usize, Vec<u8>), String>((index, bytes))
+        }));
+    }
+
+    let mut segments: Vec<(usize, Vec<u8>)> = Vec::with_capacity(total);
+    for handle in handles {
+        let (index, bytes) = handle.await.map_err(|e| format!("任务失败：{}", e))??;
+        segments.push((index, bytes));
+    }
+    segments.sort_by_key(|(i, _)| *i);
+    let ordered: Vec<Vec<u8>> = segments.into_iter().map(|(_, b)| b).collect();
+
+    let merged = if fmt == "wav" {
+        merge_wav(&ordered)?
+    } else {
+        merge_mp3(&ordered)
+    };
+
+    let path = output_path(&app, &text, fmt)?;
+    std::fs::write(&path, &merged).map_err(|e| format!("写入合并文件失败：{}", e))?;
+    crate::notify_done(&app, &format!("已合成 {} 段并合并完成", total));
+    Ok(path.to_string_lossy().into_owned())
+}
+
+/// 合成单个分块并下载其音频字节。
+async fn fetch_segment(text: String, api_key: String, fmt: String) -> Result<Vec<u8>, String> {
+    let url = crate::synthesize_speech(text, api_key, fmt).await?;
+    let bytes = reqwest::get(&url)
+        .await
+        .map_err(|e| format!("下载失败：{}", e))?
+        .bytes()
+        .await
+        .map_err(|e| format!("读取失败：{}", e))?;
+    Ok(bytes.to_vec())
+}
+
+/// 合并文件的目标路径，位于缓存目录，按完整文本哈希命名。
+fn output_path(app: &tauri::AppHandle, text: &str, fmt: &str) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| format!("无法解析缓存目录：{}", e))?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("创建缓存目录失败：{}", e))?;
+    let mut hasher = Sha256::new();
+    hasher.update(text.as_bytes());
+    Ok(dir.join(format!("long-{:x}.{}", hasher.finalize(), fmt)))
+}
+
+/// 把多个 WAV 文件的 PCM 数据拼接到同一个重写的 RIFF 头下。
+/// 采用第一个文件的 `fmt ` 块（假定所有分块采样格式一致）。
+fn merge_wav(files: &[Vec<u8>]) -> Result<Vec<u8>, String> {
+    let first = files.first().ok_or_else(|| "没有可合并的分块".to_string())?;
+    let fmt_chunk = find_chunk(first, b"fmt ").ok_or_else(|| "缺少 fmt 块".to_string())?;
+
+    let mut pcm = Vec::new();
+    for file in files {
+        if let Some(data) = find_chunk(file, b"data") {
+            pcm.extend_from_slice(data);
+        }
+    }
+
+    let data_len = pcm.len() as u32;
+    let fmt_len = fmt_chunk.len() as u32;
+    // RIFF 大小 = 4("WAVE") + (8 + fmt) + (8 + data)
+    let riff_len = 4 + (8 + fmt_len) + (8 + data_len);
+
+    let mut out = Vec::with_capacity(riff_len as usize + 8);
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&riff_len.to_le_bytes());
+    out.extend_from_slice(b"WAVE");
+    out.extend_from_slice(b"fmt ");
+    out.extend_from_slice(&fmt_len.to_le_bytes());
+    out.extend_from_slice(fmt_chunk);
+    out.extend_from_slice(b"data");
+    out.extend_from_slice(&data_len.to_le_bytes());
+    out.extend_from_slice(&pcm);
+    Ok(out)
+}
+
+/// 在 RIFF 文件中定位指定 id 的子块并返回其数据切片（不含 8 字节头）。
+fn find_chunk<'a>(file: &'a [u8], id: &[u8; 4]) -> Option<&'a [u8]> {
+    if file.len() < 12 || &file[0..4] != b"RIFF" || &file[8..12] != b"WAVE" {
+        return None;
+    }
+    let mut pos = 12;
+    while pos + 8 <= file.len() {
+        let chunk_id = &file[pos..pos + 4];
+        let size = u32::from_le_bytes([
+            file[pos + 4],
+            file[pos + 5],
+            file[pos + 6],
+            file[pos + 7],
+        ]) as usize;
+        let start = pos + 8;
+        let end = start.saturating_add(size).min(file.len());
+        if chunk_id == id {
+            return Some(&file[start..end]);
+        }
+        // 子块按 2 字节对齐。
+        pos = end + (size & 1);
+    }
+    None
+}
+
+/// 顺序拼接 MP3 帧数据，保留第一个文件开头的 ID3v2 标签，剥离后续文件的 ID3v2 头；
+/// 除末段外，各段尾部的 ID3v1 标签也会被剥离。
+fn merge_mp3(files: &[Vec<u8>]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let last = files.len().saturating_sub(1);
+    for (i, file) in files.iter().enumerate() {
+        if i == 0 {
+            // 保留首个文件开头的 ID3v2 标签，但其尾部 ID3v1 若非末段也要剥离。
+            out.extend_from_slice(strip_id3v1(file, i != last));
+        } else {
+            out.extend_from_slice(strip_id3v1(strip_id3v2(file), i != last));
+        }
+    }
+    out
+}
+
+/// 当 `drop_trailing` 为真且字节流以 128 字节的 ID3v1 `TAG` 块结尾时，
+/// 返回去掉该尾部标签后的切片，避免它被拼进合并流的中间。
+fn strip_id3v1(file: &[u8], drop_trailing: bool) -> &[u8] {
+    if drop_trailing && file.len() >= 128 {
+        let start = file.len() - 128;
+        if &file[start..start + 3] == b"TAG" {
+            return &file[..start];
+        }
+    }
+    file
+}
+
+/// 若字节流以 ID3v2 标签开头，返回去掉该标签后的切片。
+fn strip_id3v2(file: &[u8]) -> &[u8] {
+    if file.len() >= 10 && &file[0..3] == b"ID3" {
+        // 标签大小为 6..10 处的 4 字节 syncsafe 整数（每字节低 7 位有效）。
+        let size = ((file[6] as usize & 0x7f) << 21)
+            | ((file[7] as usize & 0x7f) << 14)
+            | ((file[8] as usize & 0x7f) << 7)
+            | (file[9] as usize & 0x7f);
+        let skip = (10 + size).min(file.len());
+        &file[skip..]
+    } else {
+        file
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 构造一个最小合法 WAV（16 字节 PCM `fmt ` 块 + 给定 `data`）。
+    fn wav(pcm: &[u8]) -> Vec<u8> {
+        let fmt: [u8; 16] = [
+            1, 0, // PCM
+            1, 0, // 单声道
+            0x44, 0xac, 0, 0, // 44100 采样率
+            0x88, 0x58, 1, 0, // 字节率
+            2, 0, // 块对齐
+            16, 0, // 位深
+        ];
+        let data_len = pcm.len() as u32;
+        let riff_len = 4 + (8 + 16) + (8 + data_len);
+        let mut out = Vec::new();
+        out.extend_from_slice(b"RIFF");
+        out.extend_from_slice(&riff_len.to_le_bytes());
+        out.extend_from_slice(b"WAVE");
+        out.extend_from_slice(b"fmt ");
+        out.extend_from_slice(&16u32.to_le_bytes());
+        out.extend_from_slice(&fmt);
+        out.extend_from_slice(b"data");
+        out.extend_from_slice(&data_len.to_le_bytes());
+        out.extend_from_slice(pcm);
+        out
+    }
+
+    #[test]
+    fn merge_wav_concatenates_pcm_and_rewrites_header() {
+        let a = wav(&[1, 2, 3, 4]);
+        let b = wav(&[5, 6, 7, 8, 9, 10]);
+        let merged = merge_wav(&[a, b]).unwrap();
+
+        // RIFF/WAVE 头完整。
+        assert_eq!(&merged[0..4], b"RIFF");
+        assert_eq!(&merged[8..12], b"WAVE");
+
+        // data 块应为两段 PCM 的拼接。
+        let data = find_chunk(&merged, b"data").unwrap();
+        assert_eq!(data, &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+
+        // 重写后的 data 大小与 RIFF 大小自洽。
+        let data_len = data.len() as u32;
+        let riff_len = u32::from_le_bytes([merged[4], merged[5], merged[6], merged[7]]);
+        assert_eq!(riff_len, 4 + (8 + 16) + (8 + data_len));
+        assert_eq!(merged.len() as u32, riff_len + 8);
+    }
+
+    /// 用 syncsafe 长度构造一个 ID3v2 头后跟 `payload`。
+    fn with_id3v2(payload: &[u8]) -> Vec<u8> {
+        let n = payload.len();
+        let mut out = vec![b'I', b'D', b'3', 3, 0, 0];
+        out.push(((n >> 21) & 0x7f) as u8);
+        out.push(((n >> 14) & 0x7f) as u8);
+        out.push(((n >> 7) & 0x7f) as u8);
+        out.push((n & 0x7f) as u8);
+        out.extend_from_slice(payload);
+        out
+    }
+
+    #[test]
+    fn strip_id3v2_removes_leading_tag() {
+        let framed = with_id3v2(&[0xff, 0xfb, 0x90, 0x00]);
+        assert_eq!(strip_id3v2(&framed), &[0xff, 0xfb, 0x90, 0x00]);
+        // 无标签时原样返回。
+        let bare = [0xff, 0xfb, 0x00];
+        assert_eq!(strip_id3v2(&bare), &bare);
+    }
+
+    #[test]
+    fn merge_mp3_drops_later_tags_and_trailers() {
+        // 段 1：ID3v2 头 + 帧 + 尾部 ID3v1（非末段，尾部应被剥离）。
+        let mut seg0 = with_id3v2(&[0xff, 0xfb, 0x01]);
+        seg0.extend_from_slice(b"TAG");
+        seg0.extend_from_slice(&[0u8; 125]); // 补足 128 字节的 ID3v1
+        // 段 2（末段）：ID3v2 头 + 帧 + 尾部 ID3v1（保留）。
+        let mut seg1 = with_id3v2(&[0xff, 0xfb, 0x02]);
+        seg1.extend_from_slice(b"TAG");
+        seg1.extend_from_slice(&[0u8; 125]);
+
+        let merged = merge_mp3(&[seg0, seg1]);
+
+        // 段 1 的帧保留，但其 ID3v1 尾部被剥离；段 2 的 ID3v2 头被剥离。
+        let mut expected = with_id3v2(&[0xff, 0xfb, 0x01]); // 首段保留 ID3v2
+        expected.extend_from_slice(&[0xff, 0xfb, 0x02]); // 次段仅剩帧
+        expected.extend_from_slice(b"TAG");
+        expected.extend_from_slice(&[0u8; 125]); // 末段保留 ID3v1
+        assert_eq!(merged, expected);
+    }
+
+    #[test]
+    fn split_sentences_breaks_on_boundaries() {
+        let text = format!("{}。{}！", "甲".repeat(90), "乙".repeat(90));
+        let chunks = split_sentences(&text);
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks[0].ends_with('。'));
+        assert!(chunks[1].ends_with('！'));
+    }
+}